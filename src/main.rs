@@ -1,7 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::net::{Ipv6Addr, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::{Arg, Command as ClapCommand};
 use serde::{Deserialize, Serialize};
 use tabled::{Table, Tabled, settings::{Style, Width, object::Columns}};
@@ -32,6 +36,65 @@ struct ProcessInfo {
     working_dir: String,
 }
 
+/// Processes keyed by PID plus a reverse socket-inode → PID index, both built in
+/// a single `/proc` traversal so port rows resolve their owner with one lookup.
+struct ProcessIndex {
+    processes: HashMap<u32, ProcessInfo>,
+    inode_to_pid: HashMap<u32, u32>,
+}
+
+/// A `--port` selection expressed as one or more inclusive ranges, built from a
+/// comma-separated spec like `22,80,443`, `8000-9000`, or `8000-9000,3000`.
+struct PortFilter {
+    ranges: Vec<(u16, u16)>,
+}
+
+impl PortFilter {
+    fn parse(spec: &str) -> Result<Self> {
+        let mut ranges = Vec::new();
+        for part in spec.split(',') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            // Split on '-' to tell a range from a single port, like RustScan's parse_range.
+            if let Some((start, end)) = part.split_once('-') {
+                let start: u16 = start.trim().parse()?;
+                let end: u16 = end.trim().parse()?;
+                if start > end {
+                    bail!("invalid port range '{}': start is greater than end", part);
+                }
+                ranges.push((start, end));
+            } else {
+                let port: u16 = part.parse()?;
+                ranges.push((port, port));
+            }
+        }
+        if ranges.is_empty() {
+            bail!("no ports specified");
+        }
+        Ok(PortFilter { ranges })
+    }
+
+    fn contains(&self, port: u16) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(start, end)| port >= start && port <= end)
+    }
+
+    /// Enumerate every port covered by the selection, for the connect scanner.
+    fn ports(&self) -> Vec<u16> {
+        let mut ports: Vec<u16> = self
+            .ranges
+            .iter()
+            .flat_map(|&(start, end)| start..=end)
+            .collect();
+        ports.sort_unstable();
+        ports.dedup();
+        ports
+    }
+}
+
 fn main() -> Result<()> {
     let matches = ClapCommand::new("ports-tool")
         .version("0.1.0")
@@ -55,8 +118,15 @@ fn main() -> Result<()> {
             Arg::new("port")
                 .short('p')
                 .long("port")
-                .help("Check specific port")
-                .value_name("PORT"),
+                .help("Check specific ports: a single port, list, or range (e.g. 22,80,443 or 8000-9000)")
+                .value_name("PORTS"),
+        )
+        .arg(
+            Arg::new("state")
+                .short('s')
+                .long("state")
+                .help("Show only TCP sockets in these states (e.g. LISTEN,ESTABLISHED); defaults to LISTEN")
+                .value_name("STATES"),
         )
         .arg(
             Arg::new("detailed")
@@ -86,42 +156,267 @@ fn main() -> Result<()> {
                 .help("Exclude results containing text (searches in process name, command, and working directory)")
                 .value_name("TEXT"),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Output format for results")
+                .value_name("FORMAT")
+                .value_parser(["table", "json", "jsonl"])
+                .default_value("table"),
+        )
+        .arg(
+            Arg::new("greppable")
+                .short('g')
+                .long("greppable")
+                .help("Emit one tab-separated line per port for easy grep/awk piping")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch")
+                .short('w')
+                .long("watch")
+                .help("Re-scan and redraw on a timer instead of printing once (Ctrl-C to exit)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .help("Refresh interval in seconds for watch mode")
+                .value_name("SECONDS")
+                .default_value("2"),
+        )
+        .subcommand(
+            ClapCommand::new("scan")
+                .about("Actively connect-scan a remote (or local) host over a port range")
+                .arg(
+                    Arg::new("host")
+                        .help("Host to scan (name or IP address)")
+                        .value_name("HOST")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("port")
+                        .short('p')
+                        .long("port")
+                        .help("Ports to probe: a single port, list, or range (e.g. 22,80,443 or 1-1024)")
+                        .value_name("PORTS")
+                        .default_value("1-1024"),
+                )
+                .arg(
+                    Arg::new("timeout")
+                        .short('t')
+                        .long("timeout")
+                        .help("Per-connection timeout in milliseconds")
+                        .value_name("MILLIS")
+                        .default_value("1000"),
+                )
+                .arg(
+                    Arg::new("concurrency")
+                        .long("concurrency")
+                        .help("Number of connections attempted in parallel")
+                        .value_name("N")
+                        .default_value("100"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Output format for results")
+                        .value_name("FORMAT")
+                        .value_parser(["table", "json", "jsonl"])
+                        .default_value("table"),
+                )
+                .arg(
+                    Arg::new("greppable")
+                        .short('g')
+                        .long("greppable")
+                        .help("Emit one tab-separated line per port for easy grep/awk piping")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
         .get_matches();
 
+    if let Some(scan_matches) = matches.subcommand_matches("scan") {
+        return run_scan(scan_matches);
+    }
+
     let localhost_only = matches.get_flag("localhost") || !matches.get_flag("all");
-    let specific_port: Option<u16> = matches
-        .get_one::<String>("port")
-        .and_then(|p| p.parse().ok());
+    let port_filter = match matches.get_one::<String>("port") {
+        Some(spec) => Some(PortFilter::parse(spec)?),
+        None => None,
+    };
+    let state_filter: Option<HashSet<String>> = matches.get_one::<String>("state").map(|s| {
+        s.split(',')
+            .map(|state| state.trim().to_uppercase())
+            .filter(|state| !state.is_empty())
+            .collect()
+    });
     let detailed = matches.get_flag("detailed");
     let compact = matches.get_flag("compact");
     let filter_text = matches.get_one::<String>("filter");
     let exclude_text = matches.get_one::<String>("exclude");
+    let format = matches.get_one::<String>("format").map(String::as_str).unwrap_or("table");
+    let greppable = matches.get_flag("greppable");
+    let watch = matches.get_flag("watch");
+    let interval: u64 = matches
+        .get_one::<String>("interval")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(2);
+
+    if watch {
+        // Live monitor: clear the screen and redraw every interval, exiting on Ctrl-C.
+        loop {
+            print!("\x1b[2J\x1b[1;1H");
+            let ports = collect_ports(localhost_only, port_filter.as_ref(), state_filter.as_ref(), filter_text, exclude_text)?;
+            emit_ports(&ports, detailed, compact, format, greppable)?;
+            std::thread::sleep(std::time::Duration::from_secs(interval));
+        }
+    } else {
+        let ports = collect_ports(localhost_only, port_filter.as_ref(), state_filter.as_ref(), filter_text, exclude_text)?;
+        emit_ports(&ports, detailed, compact, format, greppable)?;
+    }
+
+    Ok(())
+}
+
+fn run_scan(matches: &clap::ArgMatches) -> Result<()> {
+    let host = matches.get_one::<String>("host").expect("host is required");
+    let port_filter = PortFilter::parse(
+        matches
+            .get_one::<String>("port")
+            .map(String::as_str)
+            .unwrap_or("1-1024"),
+    )?;
+    let timeout_ms: u64 = matches
+        .get_one::<String>("timeout")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1000);
+    let concurrency: usize = matches
+        .get_one::<String>("concurrency")
+        .and_then(|s| s.parse().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(100);
+    let format = matches.get_one::<String>("format").map(String::as_str).unwrap_or("table");
+    let greppable = matches.get_flag("greppable");
+
+    let ports = scan_host(host, port_filter.ports(), timeout_ms, concurrency)?;
+    emit_ports(&ports, false, false, format, greppable)
+}
+
+fn scan_host(
+    host: &str,
+    ports: Vec<u16>,
+    timeout_ms: u64,
+    concurrency: usize,
+) -> Result<Vec<PortInfo>> {
+    let timeout = Duration::from_millis(timeout_ms);
+    let workers = concurrency.min(ports.len().max(1));
+    let host = Arc::new(host.to_string());
+    let ports = Arc::new(ports);
+    let next = Arc::new(AtomicUsize::new(0));
+    let open = Arc::new(Mutex::new(Vec::new()));
+
+    // Bounded pool: each worker pulls the next index until the list is drained,
+    // attempting a connect with a per-connection timeout and recording any that succeed.
+    let mut handles = Vec::with_capacity(workers);
+    for _ in 0..workers {
+        let host = Arc::clone(&host);
+        let ports = Arc::clone(&ports);
+        let next = Arc::clone(&next);
+        let open = Arc::clone(&open);
+        handles.push(std::thread::spawn(move || loop {
+            let idx = next.fetch_add(1, Ordering::Relaxed);
+            if idx >= ports.len() {
+                break;
+            }
+            let port = ports[idx];
+            if let Ok(mut addrs) = (host.as_str(), port).to_socket_addrs() {
+                if let Some(addr) = addrs.next() {
+                    if TcpStream::connect_timeout(&addr, timeout).is_ok() {
+                        open.lock().unwrap().push(port);
+                    }
+                }
+            }
+        }));
+    }
+    for handle in handles {
+        let _ = handle.join();
+    }
+
+    let mut open = Arc::try_unwrap(open)
+        .expect("all workers joined")
+        .into_inner()
+        .unwrap();
+    open.sort_unstable();
+
+    // Remote targets expose no process information, so those columns stay blank.
+    Ok(open
+        .into_iter()
+        .map(|port| PortInfo {
+            port,
+            protocol: "TCP".to_string(),
+            state: "OPEN".to_string(),
+            pid: "-".to_string(),
+            process_name: "-".to_string(),
+            command: "-".to_string(),
+            working_dir: "-".to_string(),
+        })
+        .collect())
+}
+
+fn emit_ports(
+    ports: &[PortInfo],
+    detailed: bool,
+    compact: bool,
+    format: &str,
+    greppable: bool,
+) -> Result<()> {
+    // Greppable and JSON modes are machine-readable, so they emit even when empty
+    // (an empty array / no lines) instead of the human "No open ports found." notice.
+    if greppable {
+        return display_greppable(ports);
+    }
+
+    match format {
+        "json" => display_json(ports),
+        "jsonl" => display_jsonl(ports),
+        _ => display_ports(ports, detailed, compact),
+    }
+}
+
+fn collect_ports(
+    localhost_only: bool,
+    port_filter: Option<&PortFilter>,
+    state_filter: Option<&HashSet<String>>,
+    filter_text: Option<&String>,
+    exclude_text: Option<&String>,
+) -> Result<Vec<PortInfo>> {
+    let mut ports = get_open_ports(localhost_only, port_filter, state_filter)?;
 
-    let mut ports = get_open_ports(localhost_only, specific_port)?;
-    
     // Apply include filter if specified
     if let Some(filter) = filter_text {
         ports = filter_ports(ports, filter);
     }
-    
+
     // Apply exclude filter if specified
     if let Some(exclude) = exclude_text {
         ports = exclude_ports(ports, exclude);
     }
-    
-    display_ports(&ports, detailed, compact)?;
 
-    Ok(())
+    Ok(ports)
 }
 
-fn get_open_ports(localhost_only: bool, specific_port: Option<u16>) -> Result<Vec<PortInfo>> {
+fn get_open_ports(
+    localhost_only: bool,
+    port_filter: Option<&PortFilter>,
+    state_filter: Option<&HashSet<String>>,
+) -> Result<Vec<PortInfo>> {
     let mut ports = Vec::new();
-    let process_map = get_process_info_map()?;
+    let process_index = get_process_index()?;
 
     // Parse /proc/net/tcp for IPv4 TCP connections
     if let Ok(tcp_content) = fs::read_to_string("/proc/net/tcp") {
         for line in tcp_content.lines().skip(1) {
-            if let Some(port_info) = parse_net_line(line, "tcp", &process_map, localhost_only, specific_port)? {
+            if let Some(port_info) = parse_net_line(line, "tcp", &process_index, localhost_only, port_filter, state_filter)? {
                 ports.push(port_info);
             }
         }
@@ -130,7 +425,25 @@ fn get_open_ports(localhost_only: bool, specific_port: Option<u16>) -> Result<Ve
     // Parse /proc/net/udp for IPv4 UDP connections
     if let Ok(udp_content) = fs::read_to_string("/proc/net/udp") {
         for line in udp_content.lines().skip(1) {
-            if let Some(port_info) = parse_net_line(line, "udp", &process_map, localhost_only, specific_port)? {
+            if let Some(port_info) = parse_net_line(line, "udp", &process_index, localhost_only, port_filter, state_filter)? {
+                ports.push(port_info);
+            }
+        }
+    }
+
+    // Parse /proc/net/tcp6 for IPv6 TCP connections
+    if let Ok(tcp6_content) = fs::read_to_string("/proc/net/tcp6") {
+        for line in tcp6_content.lines().skip(1) {
+            if let Some(port_info) = parse_net_line(line, "tcp6", &process_index, localhost_only, port_filter, state_filter)? {
+                ports.push(port_info);
+            }
+        }
+    }
+
+    // Parse /proc/net/udp6 for IPv6 UDP connections
+    if let Ok(udp6_content) = fs::read_to_string("/proc/net/udp6") {
+        for line in udp6_content.lines().skip(1) {
+            if let Some(port_info) = parse_net_line(line, "udp6", &process_index, localhost_only, port_filter, state_filter)? {
                 ports.push(port_info);
             }
         }
@@ -143,9 +456,10 @@ fn get_open_ports(localhost_only: bool, specific_port: Option<u16>) -> Result<Ve
 fn parse_net_line(
     line: &str,
     protocol: &str,
-    process_map: &HashMap<u32, ProcessInfo>,
+    process_index: &ProcessIndex,
     localhost_only: bool,
-    specific_port: Option<u16>,
+    port_filter: Option<&PortFilter>,
+    state_filter: Option<&HashSet<String>>,
 ) -> Result<Option<PortInfo>> {
     let fields: Vec<&str> = line.split_whitespace().collect();
     if fields.len() < 10 {
@@ -162,31 +476,40 @@ fn parse_net_line(
     }
 
     let port = u16::from_str_radix(addr_parts[1], 16).unwrap_or(0);
-    let addr = u32::from_str_radix(addr_parts[0], 16).unwrap_or(0);
 
-    // Convert to IP address (little-endian)
-    let ip = format!(
-        "{}.{}.{}.{}",
-        addr & 0xFF,
-        (addr >> 8) & 0xFF,
-        (addr >> 16) & 0xFF,
-        (addr >> 24) & 0xFF
-    );
+    // IPv6 sockets store a 128-bit address as 32 hex chars; IPv4 uses 8.
+    let is_ipv6 = protocol.ends_with('6');
+    let is_localhost = if is_ipv6 {
+        // ::1 is loopback and :: is the wildcard, mirroring 127.0.0.1/0.0.0.0.
+        let ip = parse_ipv6_addr(addr_parts[0]).unwrap_or(Ipv6Addr::UNSPECIFIED);
+        ip.is_loopback() || ip.is_unspecified()
+    } else {
+        let addr = u32::from_str_radix(addr_parts[0], 16).unwrap_or(0);
+        // Convert to IP address (little-endian)
+        let ip = format!(
+            "{}.{}.{}.{}",
+            addr & 0xFF,
+            (addr >> 8) & 0xFF,
+            (addr >> 16) & 0xFF,
+            (addr >> 24) & 0xFF
+        );
+        ip == "127.0.0.1" || ip == "0.0.0.0"
+    };
 
     // Filter for localhost if requested
-    if localhost_only && ip != "127.0.0.1" && ip != "0.0.0.0" {
+    if localhost_only && !is_localhost {
         return Ok(None);
     }
 
-    // Filter for specific port if requested
-    if let Some(target_port) = specific_port {
-        if port != target_port {
+    // Filter by the requested port set/ranges if given
+    if let Some(filter) = port_filter {
+        if !filter.contains(port) {
             return Ok(None);
         }
     }
 
     // For TCP, only show listening ports (state 0A = LISTEN)
-    let state_str = if protocol == "tcp" {
+    let state_str = if protocol.starts_with("tcp") {
         match state {
             "0A" => "LISTEN",
             "01" => "ESTABLISHED",
@@ -205,17 +528,28 @@ fn parse_net_line(
         "OPEN"
     };
 
-    // For TCP, we mainly want listening ports
-    if protocol == "tcp" && state != "0A" && specific_port.is_none() {
-        return Ok(None);
+    // For TCP, default to listening sockets only; --state opts into other states.
+    // (UDP has no connection state, so it is always shown.)
+    if protocol.starts_with("tcp") {
+        match state_filter {
+            Some(states) => {
+                if !states.contains(state_str) {
+                    return Ok(None);
+                }
+            }
+            None => {
+                if state != "0A" {
+                    return Ok(None);
+                }
+            }
+        }
     }
 
     // Try to get the inode to find the process
     let inode: u32 = fields.get(9).unwrap_or(&"0").parse().unwrap_or(0);
-    let (pid, process_info) = if inode > 0 {
-        find_process_by_inode(inode, process_map)?
-    } else {
-        (None, None)
+    let (pid, process_info) = match process_index.inode_to_pid.get(&inode) {
+        Some(&owner) if inode > 0 => (Some(owner), process_index.processes.get(&owner)),
+        _ => (None, None),
     };
 
     Ok(Some(PortInfo {
@@ -229,20 +563,60 @@ fn parse_net_line(
     }))
 }
 
-fn get_process_info_map() -> Result<HashMap<u32, ProcessInfo>> {
-    let mut process_map = HashMap::new();
+fn parse_ipv6_addr(hex: &str) -> Option<Ipv6Addr> {
+    // /proc stores the 128-bit address as four 32-bit little-endian words, so
+    // each 8-hex-char group decodes to four bytes in reversed order.
+    if hex.len() != 32 {
+        return None;
+    }
+    let mut bytes = [0u8; 16];
+    for word in 0..4 {
+        let chunk = &hex[word * 8..word * 8 + 8];
+        let value = u32::from_str_radix(chunk, 16).ok()?;
+        bytes[word * 4..word * 4 + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    Some(Ipv6Addr::from(bytes))
+}
+
+fn get_process_index() -> Result<ProcessIndex> {
+    let mut processes = HashMap::new();
+    let mut inode_to_pid = HashMap::new();
 
     if let Ok(proc_dir) = fs::read_dir("/proc") {
         for entry in proc_dir.flatten() {
             if let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() {
                 if let Ok(process_info) = get_process_info(pid) {
-                    process_map.insert(pid, process_info);
+                    // Index every socket inode this PID holds open in the same pass,
+                    // so a port row resolves its owner with a single lookup.
+                    let fd_dir = format!("/proc/{}/fd", pid);
+                    if let Ok(entries) = fs::read_dir(&fd_dir) {
+                        for fd in entries.flatten() {
+                            if let Ok(link_target) = fs::read_link(fd.path()) {
+                                if let Some(inode) =
+                                    link_target.to_str().and_then(parse_socket_inode)
+                                {
+                                    inode_to_pid.insert(inode, pid);
+                                }
+                            }
+                        }
+                    }
+                    processes.insert(pid, process_info);
                 }
             }
         }
     }
 
-    Ok(process_map)
+    Ok(ProcessIndex {
+        processes,
+        inode_to_pid,
+    })
+}
+
+fn parse_socket_inode(target: &str) -> Option<u32> {
+    target
+        .strip_prefix("socket:[")
+        .and_then(|s| s.strip_suffix(']'))
+        .and_then(|s| s.parse().ok())
 }
 
 fn get_process_info(pid: u32) -> Result<ProcessInfo> {
@@ -278,36 +652,6 @@ fn get_process_info(pid: u32) -> Result<ProcessInfo> {
     })
 }
 
-fn find_process_by_inode(
-    target_inode: u32,
-    process_map: &HashMap<u32, ProcessInfo>,
-) -> Result<(Option<u32>, Option<ProcessInfo>)> {
-    for (pid, process_info) in process_map {
-        let fd_dir = format!("/proc/{}/fd", pid);
-        if let Ok(entries) = fs::read_dir(&fd_dir) {
-            for entry in entries.flatten() {
-                if let Ok(link_target) = fs::read_link(entry.path()) {
-                    if let Some(target_str) = link_target.to_str() {
-                        if target_str.starts_with("socket:[") {
-                            if let Some(inode_str) = target_str
-                                .strip_prefix("socket:[")
-                                .and_then(|s| s.strip_suffix(']'))
-                            {
-                                if let Ok(inode) = inode_str.parse::<u32>() {
-                                    if inode == target_inode {
-                                        return Ok((Some(*pid), Some(process_info.clone())));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-    Ok((None, None))
-}
-
 fn filter_ports(ports: Vec<PortInfo>, filter_text: &str) -> Vec<PortInfo> {
     let filter_lower = filter_text.to_lowercase();
     ports.into_iter().filter(|port| {
@@ -341,6 +685,34 @@ fn display_ports(ports: &[PortInfo], detailed: bool, compact: bool) -> Result<()
     }
 }
 
+fn display_json(ports: &[PortInfo]) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(ports)?);
+    Ok(())
+}
+
+fn display_jsonl(ports: &[PortInfo]) -> Result<()> {
+    for port in ports {
+        println!("{}", serde_json::to_string(port)?);
+    }
+    Ok(())
+}
+
+fn display_greppable(ports: &[PortInfo]) -> Result<()> {
+    for port in ports {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            port.port,
+            port.protocol,
+            port.state,
+            port.pid,
+            port.process_name,
+            port.command,
+            port.working_dir
+        );
+    }
+    Ok(())
+}
+
 fn display_detailed_format(ports: &[PortInfo]) -> Result<()> {
     for (i, port) in ports.iter().enumerate() {
         if i > 0 {